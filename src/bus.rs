@@ -0,0 +1,58 @@
+// Address space abstraction so `CPU` doesn't have to know how memory is laid out.
+
+/// Anything that can be read from and written to by address.
+///
+/// Implementing this (instead of handing the `CPU` a raw array) lets a host
+/// map in ROM/RAM splits, mirrored regions, or memory-mapped I/O without
+/// touching CPU code.
+pub trait Bus {
+    fn read(&self, addr: u16) -> u8;
+    fn write(&mut self, addr: u16, data: u8);
+
+    fn read_u16(&self, addr: u16) -> u16 {
+        let lo = self.read(addr) as u16;
+        let hi = self.read(addr.wrapping_add(1)) as u16;
+        (hi << 8) | lo
+    }
+
+    fn write_u16(&mut self, addr: u16, data: u16) {
+        let hi = (data >> 8) as u8;
+        let lo = (data & 0xff) as u8;
+        self.write(addr, lo);
+        self.write(addr.wrapping_add(1), hi);
+    }
+
+    /// Dumps the full `0x0000..=0xFFFF` address space through `read`, so any
+    /// implementor can be snapshotted without exposing its internals.
+    fn snapshot(&self) -> Vec<u8> {
+        (0..=u16::MAX).map(|addr| self.read(addr)).collect()
+    }
+
+    /// Restores a snapshot produced by `snapshot`, writing it back through `write`.
+    fn restore(&mut self, data: &[u8]) {
+        for (addr, byte) in data.iter().enumerate().take(0x10000) {
+            self.write(addr as u16, *byte);
+        }
+    }
+}
+
+/// The default flat `0x0000..=0xFFFF` address space, backed by a plain array.
+pub struct Memory {
+    data: [u8; 0x10000],
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Memory { data: [0; 0x10000] }
+    }
+}
+
+impl Bus for Memory {
+    fn read(&self, addr: u16) -> u8 {
+        self.data[addr as usize]
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.data[addr as usize] = data;
+    }
+}
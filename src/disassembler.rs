@@ -0,0 +1,72 @@
+// Decodes opcode bytes into human-readable 6502 assembly, reusing the
+// mnemonic/mode table that drives `CPU::run`.
+
+use crate::opcodes::{opcode_table, AddressingMode};
+
+fn format_instruction(mnemonic: &str, mode: AddressingMode, operand: &[u8]) -> String {
+    match mode {
+        AddressingMode::Implied => mnemonic.to_string(),
+        AddressingMode::Accumulator => format!("{} A", mnemonic),
+        AddressingMode::Immediate => format!("{} #${:02X}", mnemonic, operand[0]),
+        AddressingMode::ZeroPage => format!("{} ${:02X}", mnemonic, operand[0]),
+        AddressingMode::ZeroPageX => format!("{} ${:02X},X", mnemonic, operand[0]),
+        AddressingMode::ZeroPageY => format!("{} ${:02X},Y", mnemonic, operand[0]),
+        AddressingMode::Absolute => {
+            format!("{} ${:04X}", mnemonic, u16::from_le_bytes([operand[0], operand[1]]))
+        }
+        AddressingMode::AbsoluteX => {
+            format!("{} ${:04X},X", mnemonic, u16::from_le_bytes([operand[0], operand[1]]))
+        }
+        AddressingMode::AbsoluteY => {
+            format!("{} ${:04X},Y", mnemonic, u16::from_le_bytes([operand[0], operand[1]]))
+        }
+        AddressingMode::IndirectX => format!("{} (${:02X},X)", mnemonic, operand[0]),
+        AddressingMode::IndirectY => format!("{} (${:02X}),Y", mnemonic, operand[0]),
+        AddressingMode::Indirect => {
+            format!("{} (${:04X})", mnemonic, u16::from_le_bytes([operand[0], operand[1]]))
+        }
+        AddressingMode::Relative => {
+            let offset = operand[0] as i8;
+            if offset >= 0 {
+                format!("{} +${:02X}", mnemonic, offset)
+            } else {
+                format!("{} -${:02X}", mnemonic, offset.unsigned_abs())
+            }
+        }
+    }
+}
+
+/// Decodes `count` instructions out of `bytes`, labeling each with the
+/// address it would occupy starting at `start`. Bytes that aren't a known
+/// opcode are emitted as a raw `.byte` directive so disassembly can resync
+/// on the next instruction boundary.
+pub fn disassemble(bytes: &[u8], start: u16, count: usize) -> Vec<(u16, String)> {
+    let mut lines = Vec::with_capacity(count);
+    let mut offset = 0usize;
+    let mut addr = start;
+
+    for _ in 0..count {
+        if offset >= bytes.len() {
+            break;
+        }
+
+        let opcode_byte = bytes[offset];
+        match opcode_table().get(&opcode_byte) {
+            Some(op) => {
+                let operand: Vec<u8> = (1..op.len as usize)
+                    .map(|i| *bytes.get(offset + i).unwrap_or(&0))
+                    .collect();
+                lines.push((addr, format_instruction(op.mnemonic, op.mode, &operand)));
+                offset += op.len as usize;
+                addr = addr.wrapping_add(op.len as u16);
+            }
+            None => {
+                lines.push((addr, format!(".byte ${:02X}", opcode_byte)));
+                offset += 1;
+                addr = addr.wrapping_add(1);
+            }
+        }
+    }
+
+    lines
+}